@@ -4,7 +4,7 @@ use std::fs::File;
 use std::io::Write;
 use std::process::{self, Command, Stdio};
 
-use clap::{Parser, ValueEnum};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use colored::Colorize;
 use rand::{random_range, Rng};
 use serde::Deserialize;
@@ -54,6 +54,16 @@ struct MediaFormats {
 	gifpreview: MediaInfo
 }
 
+#[derive(Deserialize, Debug)]
+struct MastodonMedia {
+	id: String
+}
+
+#[derive(Deserialize, Debug)]
+struct MastodonStatus {
+	url: String
+}
+
 #[derive(Deserialize, Debug)]
 struct MediaInfo {
 	url: String,
@@ -73,9 +83,12 @@ struct MediaInfo {
 	"-- copy a random link from the first 5 results, with no output".bold(),
 	"-- list 15 gif links".bold(),
 	"-- copy a random link from the first 10 results with a very small resolution".bold()
-), long_about = None)]
-	
+), long_about = None, subcommand_negates_reqs = true)]
+
 struct Cli {
+	#[command(subcommand)]
+	command: Option<Commands>,
+
 	/// Number of items to list
 	#[arg(long, short, default_value_t = 10, value_parser = clap::value_parser!(u8).range(1..=50))]
 	limit: u8,
@@ -88,6 +101,34 @@ struct Cli {
 	#[arg(long, short, default_value_t = false)]
 	save_random: bool,
 
+	/// With -c, put the actual image bytes on the clipboard instead of the URL
+	#[arg(long, default_value_t = false)]
+	copy_image: bool,
+
+	/// Drop results whose chosen resolution is larger than this (e.g. 500k, 2M)
+	#[arg(long, value_parser = parse_size)]
+	max_size: Option<u32>,
+
+	/// Drop results whose chosen resolution is smaller than this (e.g. 500k, 2M)
+	#[arg(long, value_parser = parse_size)]
+	min_size: Option<u32>,
+
+	/// Upload a random gif to the configured Mastodon instance and publish a status
+	#[arg(long, default_value_t = false)]
+	post: bool,
+
+	/// Status body used by --post (defaults to the gif's content description)
+	#[arg(long)]
+	caption: Option<String>,
+
+	/// Render each result inline in the terminal instead of (or alongside) printing links
+	#[arg(long, short, default_value_t = false)]
+	preview: bool,
+
+	/// Cap on the rendered height (in terminal cells) used by --preview; the width follows the aspect ratio
+	#[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u32).range(1..=200))]
+	preview_size: u32,
+
 	/// Don't print anything to stdout (except errors and debug)
 	#[arg(long, short, default_value_t = false)]
 	quiet: bool,
@@ -108,15 +149,36 @@ struct Cli {
 	#[arg(long, short, default_value_t = false)]
 	debug: bool,
 
+	/// Transcode a saved gif (with -s) to another format by piping it through ffmpeg
+	#[arg(long, value_enum)]
+	convert: Option<ConvertFormat>,
+
 	/// Set a v2 api key that you got from Google here: https://developers.google.com/tenor/guides/quickstart
 	#[arg(long)]
 	set_api_key: Option<String>,
 
+	/// Set the Mastodon/Fediverse access token used by --post
+	#[arg(long)]
+	set_mastodon_token: Option<String>,
+
+	/// Set the Mastodon/Fediverse instance base url used by --post (e.g. https://mastodon.social)
+	#[arg(long)]
+	set_mastodon_instance: Option<String>,
+
 	/// A search term to query the tenor api
-	#[arg(required_unless_present = "set_api_key")]
+	#[arg(required_unless_present_any = ["set_api_key", "set_mastodon_token", "set_mastodon_instance"])]
 	query: Vec<String>,
 }
 
+#[derive(Subcommand, Debug)]
+enum Commands {
+	/// Print a shell completion script to stdout
+	Completions {
+		/// The shell to generate completions for
+		shell: clap_complete::Shell,
+	},
+}
+
 #[derive(Debug)]
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
 enum URLType {
@@ -154,6 +216,68 @@ enum GifResolution {
 	NanoWebm,
 }
 
+#[derive(Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum ConvertFormat {
+	Mp4,
+	Webm,
+	Webp,
+	Apng,
+	Gif,
+}
+
+impl ConvertFormat {
+	/// File extension written to the Pictures dir for this format
+	fn extension(&self) -> &'static str {
+		match self {
+			ConvertFormat::Mp4 => "mp4",
+			ConvertFormat::Webm => "webm",
+			ConvertFormat::Webp => "webp",
+			ConvertFormat::Apng => "apng",
+			ConvertFormat::Gif => "gif",
+		}
+	}
+
+	/// Output arguments handed to ffmpeg. mp4 is fragmented so the moov atom
+	/// doesn't have to be seeked, which lets us mux straight to a pipe.
+	fn ffmpeg_args(&self) -> &'static [&'static str] {
+		match self {
+			ConvertFormat::Mp4 => &["-movflags", "frag_keyframe+empty_moov", "-f", "mp4"],
+			ConvertFormat::Webm => &["-f", "webm"],
+			ConvertFormat::Webp => &["-f", "webp"],
+			ConvertFormat::Apng => &["-f", "apng"],
+			ConvertFormat::Gif => &["-f", "gif"],
+		}
+	}
+}
+
+/// Pipe `bytes` through a spawned ffmpeg, reading from stdin and muxing the
+/// requested container to stdout. ffmpeg streams output as it encodes, so stdin
+/// is fed from a separate thread while the parent drains stdout; writing it all
+/// up front would deadlock once ffmpeg fills the stdout pipe.
+fn transcode_with_ffmpeg(bytes: &[u8], format: ConvertFormat) -> Result<Vec<u8>, std::io::Error> {
+	let mut child = Command::new("ffmpeg")
+		.args(["-hide_banner", "-loglevel", "error", "-i", "pipe:0"])
+		.args(format.ffmpeg_args())
+		.arg("pipe:1")
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::inherit())
+		.spawn()?;
+
+	let mut stdin = child.stdin.take().unwrap();
+	let input = bytes.to_vec();
+	let writer = std::thread::spawn(move || stdin.write_all(&input));
+
+	let output = child.wait_with_output()?;
+	//surface a write error before the exit status so the cause isn't masked
+	writer.join().expect("ffmpeg stdin writer thread panicked")?;
+	if !output.status.success() {
+		return Err(std::io::Error::other("ffmpeg exited with a non-zero status"));
+	}
+	Ok(output.stdout)
+}
+
 fn x11_copy_to_clipboard(text: &str) -> Result<(), std::io::Error> {
 	let mut child = Command::new("xclip")
 		.args(["-sel", "clip"])
@@ -191,41 +315,282 @@ fn macos_copy_to_clipboard(text: &str) -> Result<(), std::io::Error> {
 	Ok(())
 }
 
+/// Number of times a preview animation is cycled before moving on
+const PREVIEW_LOOPS: u32 = 1;
+
+/// Decode the downloaded gif bytes into frames and blit them inline with viuer,
+/// cycling the animation PREVIEW_LOOPS times. `restore_cursor` returns the
+/// cursor to the frame's top-left after each print so frames overwrite in place
+/// instead of stacking into a filmstrip, and only the height is capped so the
+/// source aspect ratio is preserved. viuer uses the Kitty or iTerm2 graphics
+/// protocol where the terminal supports it and falls back to half-block unicode
+/// otherwise; sixel output is not used, as viuer's `sixel` feature is not enabled.
+/// Errors are non-fatal so a single bad frame or an unsupported codec doesn't
+/// abort the whole listing.
+async fn preview_media(bytes: &[u8], max_cells: u32) -> Result<(), Box<dyn std::error::Error>> {
+	use image::AnimationDecoder;
+	use image::codecs::gif::GifDecoder;
+	use std::io::Cursor;
+
+	let conf = viuer::Config {
+		height: Some(max_cells),
+		absolute_offset: false,
+		restore_cursor: true,
+		..Default::default()
+	};
+
+	let frames = GifDecoder::new(Cursor::new(bytes))?.into_frames().collect_frames()?;
+	let mut printed_height = 0;
+	for _ in 0..PREVIEW_LOOPS {
+		for frame in &frames {
+			//restore_cursor returns the cursor to the frame's top-left after each
+			//print, so the next frame overwrites this one in place
+			let img = image::DynamicImage::ImageRgba8(frame.buffer().clone());
+			(_, printed_height) = viuer::print(&img, &conf)?;
+			let (numer, denom) = frame.delay().numer_denom_ms();
+			tokio::time::sleep(std::time::Duration::from_millis((numer / denom.max(1)) as u64)).await;
+		}
+	}
+	//move the cursor below the last frame so following output doesn't clobber it
+	for _ in 0..printed_height {
+		println!();
+	}
+	Ok(())
+}
+
+/// Download the raw bytes at `url`, reusing the shared api user agent
+async fn download_bytes(client: &reqwest::Client, url: &str) -> Result<bytes::Bytes, Error> {
+	client
+		.get(url)
+		.header(USER_AGENT, "rust-web-api-client")
+		.send()
+		.await?
+		.bytes()
+		.await
+}
+
+/// Best-effort MIME type for a media url based on its extension, defaulting to
+/// image/gif which is what the default resolution serves.
+fn mime_for_url(url: &str) -> &'static str {
+	match url.rsplit('.').next().map(|e| e.to_lowercase()) {
+		Some(ext) if ext == "webp" => "image/webp",
+		Some(ext) if ext == "png" => "image/png",
+		Some(ext) if ext == "mp4" => "video/mp4",
+		Some(ext) if ext == "webm" => "video/webm",
+		_ => "image/gif",
+	}
+}
+
+fn x11_copy_image_to_clipboard(bytes: &[u8], mime: &str) -> Result<(), std::io::Error> {
+	let mut child = Command::new("xclip")
+		.args(["-selection", "clipboard", "-t", mime, "-i"])
+		.stdin(Stdio::piped())
+		.spawn()?;
+
+	child.stdin.as_mut().unwrap().write_all(bytes)?;
+	Ok(())
+}
+
+fn wayland_copy_image_to_clipboard(bytes: &[u8], mime: &str) -> Result<(), std::io::Error> {
+	let mut child = Command::new("wl-copy")
+		.args(["--type", mime])
+		.stdin(Stdio::piped())
+		.spawn()?;
+
+	child.stdin.as_mut().unwrap().write_all(bytes)?;
+	Ok(())
+}
+
+/// Place image data on the native clipboard via arboard, used on macOS/Windows
+/// whose `pbcopy`/`clip` are text-only. arboard holds a still raster, so the
+/// first frame of an animated gif is decoded and handed over.
+fn native_copy_image_to_clipboard(bytes: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+	let frame = image::load_from_memory(bytes)?.to_rgba8();
+	let (width, height) = frame.dimensions();
+	let image_data = arboard::ImageData {
+		width: width as usize,
+		height: height as usize,
+		bytes: std::borrow::Cow::Owned(frame.into_raw()),
+	};
+	arboard::Clipboard::new()?.set_image(image_data)?;
+	Ok(())
+}
+
+fn get_requested_media_info(gif: &Gif, resolution: GifResolution) -> &MediaInfo {
+	match resolution {
+		GifResolution::Gif => &gif.media_formats.gif,
+		GifResolution::MediumGif => &gif.media_formats.mediumgif,
+		GifResolution::TinyGif => &gif.media_formats.tinygif,
+		GifResolution::NanoGif => &gif.media_formats.nanogif,
+		GifResolution::Webp => &gif.media_formats.webp,
+		GifResolution::GifPreview => &gif.media_formats.gifpreview,
+		GifResolution::TinyGifPreview => &gif.media_formats.tinygifpreview,
+		GifResolution::NanoGifPreview => &gif.media_formats.nanogifpreview,
+		GifResolution::Mp4 => &gif.media_formats.mp4,
+		GifResolution::LoopedMp4 => &gif.media_formats.loopedmp4,
+		GifResolution::TinyMp4 => &gif.media_formats.tinymp4,
+		GifResolution::NanoMp4 => &gif.media_formats.nanomp4,
+		GifResolution::Webm => &gif.media_formats.webm,
+		GifResolution::TinyWebm => &gif.media_formats.tinywebm,
+		GifResolution::NanoWebm => &gif.media_formats.nanowebm,
+	}
+}
+
 fn get_requested_media_url<'a>(gif: &'a Gif, resolution: GifResolution) -> &'a std::string::String {
-	return match resolution {
-		GifResolution::Gif => &gif.media_formats.gif.url,
-		GifResolution::MediumGif => &gif.media_formats.mediumgif.url,
-		GifResolution::TinyGif => &gif.media_formats.tinygif.url,
-		GifResolution::NanoGif => &gif.media_formats.nanogif.url,
-		GifResolution::Webp => &gif.media_formats.webp.url,
-		GifResolution::GifPreview => &gif.media_formats.gifpreview.url,
-		GifResolution::TinyGifPreview => &gif.media_formats.tinygifpreview.url,
-		GifResolution::NanoGifPreview => &gif.media_formats.nanogifpreview.url,
-		GifResolution::Mp4 => &gif.media_formats.mp4.url,
-		GifResolution::LoopedMp4 => &gif.media_formats.loopedmp4.url,
-		GifResolution::TinyMp4 => &gif.media_formats.tinymp4.url,
-		GifResolution::NanoMp4 => &gif.media_formats.nanomp4.url,
-		GifResolution::Webm => &gif.media_formats.webm.url,
-		GifResolution::TinyWebm => &gif.media_formats.tinywebm.url,
-		GifResolution::NanoWebm => &gif.media_formats.nanowebm.url,
+	return &get_requested_media_info(gif, resolution).url;
+}
+
+/// Parse a human friendly byte size such as `500k` or `2M` into a raw byte
+/// count. A bare number is taken as bytes; the `k`/`m`/`g` suffixes (case
+/// insensitive) are 1024-based.
+fn parse_size(raw: &str) -> Result<u32, String> {
+	let raw = raw.trim();
+	let (number, multiplier) = match raw.chars().last() {
+		Some('k') | Some('K') => (&raw[..raw.len() - 1], 1024),
+		Some('m') | Some('M') => (&raw[..raw.len() - 1], 1024 * 1024),
+		Some('g') | Some('G') => (&raw[..raw.len() - 1], 1024 * 1024 * 1024),
+		_ => (raw, 1),
+	};
+	number
+		.trim()
+		.parse::<u32>()
+		.map_err(|_| format!("\"{}\" is not a valid size, try something like 500k or 2M", raw))?
+		.checked_mul(multiplier)
+		.ok_or_else(|| format!("\"{}\" is too large", raw))
+}
+
+/// Insert or replace a single `KEY=value` line in the config file, leaving any
+/// other settings that are already there untouched.
+fn set_config_value(path: &Path, key: &str, value: &str) -> std::io::Result<()> {
+	let prefix = format!("{key}=");
+	let mut lines: Vec<String> = if path.exists() {
+		std::fs::read_to_string(path)?
+			.lines()
+			.filter(|line| !line.starts_with(&prefix))
+			.map(|line| line.to_string())
+			.collect()
+	} else {
+		Vec::new()
 	};
+	lines.push(format!("{key}={value}"));
+	std::fs::write(path, lines.join("\n"))
+}
+
+/// How many times media processing is polled (at 1s each) before giving up
+const MASTODON_MEDIA_POLLS: u32 = 30;
+
+/// Upload media to a Mastodon instance, wait for it to finish processing, then
+/// publish a status referencing it. Returns the url of the created status.
+#[allow(clippy::too_many_arguments)]
+async fn post_to_mastodon(
+	client: &reqwest::Client,
+	instance: &str,
+	token: &str,
+	bytes: bytes::Bytes,
+	mime: &str,
+	filename: &str,
+	alt: &str,
+	status: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+	let instance = instance.trim_end_matches('/');
+
+	//upload the media
+	let part = reqwest::multipart::Part::bytes(bytes.to_vec())
+		.file_name(filename.to_string())
+		.mime_str(mime)?;
+	let form = reqwest::multipart::Form::new()
+		.part("file", part)
+		.text("description", alt.to_string());
+	let media: MastodonMedia = client
+		.post(format!("{instance}/api/v2/media"))
+		.bearer_auth(token)
+		.multipart(form)
+		.send()
+		.await?
+		.error_for_status()?
+		.json()
+		.await?;
+
+	//poll until the instance finishes transcoding (anything but a 200 means still processing),
+	//bounded so a stuck item doesn't hang the cli forever
+	let mut done = false;
+	for _ in 0..MASTODON_MEDIA_POLLS {
+		let response = client
+			.get(format!("{instance}/api/v1/media/{}", media.id))
+			.bearer_auth(token)
+			.send()
+			.await?
+			.error_for_status()?;
+		if response.status().as_u16() == 200 {
+			done = true;
+			break;
+		}
+		tokio::time::sleep(std::time::Duration::from_millis(1000)).await;
+	}
+	if !done {
+		return Err("Mastodon media never finished processing".into());
+	}
+
+	//create the status referencing the uploaded media
+	let posted: MastodonStatus = client
+		.post(format!("{instance}/api/v1/statuses"))
+		.bearer_auth(token)
+		.form(&[("status", status), ("media_ids[]", media.id.as_str())])
+		.send()
+		.await?
+		.error_for_status()?
+		.json()
+		.await?;
+
+	Ok(posted.url)
 }
 
 #[tokio::main]
 async fn main () -> Result<(), Error> {
 	let config_filename = dirs_next::config_dir().expect("dirs_next couldnt get a config dir").join("tenorcli.conf");
 	let args = Cli::parse();
+
+	if let Some(Commands::Completions { shell }) = &args.command {
+		let mut cmd = Cli::command();
+		let bin_name = cmd.get_name().to_string();
+		clap_complete::generate(*shell, &mut cmd, bin_name, &mut std::io::stdout());
+		process::exit(0);
+	}
+
 	if args.set_api_key.is_some() {
-		let mut file = File::create(&config_filename).unwrap();
-		file.write_all(format!("API_KEY={}", args.set_api_key.as_ref().unwrap()).as_bytes()).expect("Couldnt write to config file");
+		set_config_value(&config_filename, "API_KEY", args.set_api_key.as_ref().unwrap()).expect("Couldnt write to config file");
 		println!("New API key was written to {:?}", config_filename);
 		process::exit(0);
 	}
-	
+
+	if let Some(token) = &args.set_mastodon_token {
+		set_config_value(&config_filename, "MASTODON_TOKEN", token).expect("Couldnt write to config file");
+		println!("Mastodon token was written to {:?}", config_filename);
+		process::exit(0);
+	}
+
+	if let Some(instance) = &args.set_mastodon_instance {
+		set_config_value(&config_filename, "MASTODON_INSTANCE", instance).expect("Couldnt write to config file");
+		println!("Mastodon instance was written to {:?}", config_filename);
+		process::exit(0);
+	}
+
+	//--copy-image only modifies the copy action, so it's meaningless on its own
+	if args.copy_image && !args.copy_random {
+		eprintln!("--copy-image only has an effect together with -c/--copy-random");
+		process::exit(1);
+	}
+
+	//--convert only modifies the save action, so it's meaningless on its own
+	if args.convert.is_some() && !args.save_random {
+		eprintln!("--convert only has an effect together with -s/--save-random");
+		process::exit(1);
+	}
+
 	if config_filename.exists() {
 		dotenv::from_filename(config_filename).ok();
 	}
-	
+
 	let key = std::env::var("API_KEY").expect(format!("{}", "An API key is required. Get one from https://developers.google.com/tenor/guides/quickstart and set it with --set-api-key <TOKEN>".bold()).as_str());
 	
 	// let mut stdin_query = String::new();
@@ -262,7 +627,19 @@ async fn main () -> Result<(), Error> {
 
 	// println!("{}", response.status());
 	let result: ApiResult = response.json().await?;
-	let gifs: Vec<Gif> = result.results;
+	let mut gifs: Vec<Gif> = result.results;
+
+	//filter on the chosen resolution's byte size before anything consumes the list
+	if args.max_size.is_some() || args.min_size.is_some() {
+		gifs.retain(|gif| {
+			let size = get_requested_media_info(gif, args.resolution).size;
+			args.max_size.is_none_or(|max| size <= max) && args.min_size.is_none_or(|min| size >= min)
+		});
+		if gifs.is_empty() {
+			eprintln!("No results left after filtering by size, try loosening --max-size/--min-size");
+			process::exit(1);
+		}
+	}
 
 	if !args.quiet {
 		//print the array
@@ -283,8 +660,19 @@ async fn main () -> Result<(), Error> {
 			}
 		}
 	}
+
+	if args.preview {
+		//download a small format for each result and render it inline
+		for gif in &gifs {
+			let preview_url = &gif.media_formats.tinygif.url;
+			let bytes = download_bytes(&client, preview_url).await?;
+			if let Err(e) = preview_media(&bytes, args.preview_size).await {
+				eprintln!("Couldn't preview {}: {e}", preview_url);
+			}
+		}
+	}
 	
-	if args.copy_random || args.save_random {
+	if args.copy_random || args.save_random || args.post {
 		let max = gifs.len();
 		let idx = rand::rng().random_range(0..max);
 		let random_gif = &gifs[idx];
@@ -293,12 +681,49 @@ async fn main () -> Result<(), Error> {
 		let supported_os = ["linux", "openbsd", "freebsd", "netbsd", "windows", "macos"];
 		let os = env::consts::OS;
 
-		if !supported_os.contains(&os) {
+		if args.preview {
+			let preview_url = &random_gif.media_formats.tinygif.url;
+			let bytes = download_bytes(&client, preview_url).await?;
+			if let Err(e) = preview_media(&bytes, args.preview_size).await {
+				eprintln!("Couldn't preview {}: {e}", preview_url);
+			}
+		}
+
+		if (args.copy_random || args.save_random) && !supported_os.contains(&os) {
 			eprintln!("Unsupported os \"{}\" for the copy function. Supported operating systems are {:?}\nHeres your random link: {}", os, supported_os, &random_gif_link);
 			process::exit(1);
 		}
 
-		if args.copy_random {
+		if args.copy_random && args.copy_image {
+			//put the actual media bytes on the clipboard instead of the url
+			let image_bytes = download_bytes(&client, gif_direct_link).await?;
+			let mime = mime_for_url(gif_direct_link);
+			match os {
+				"linux"|"openbsd"|"freebsd"|"netbsd" => {
+					if env::var_os("DISPLAY").is_some() {
+						if let Err(e) = x11_copy_image_to_clipboard(&image_bytes, mime) {
+							eprintln!("An error occured when calling `xclip`: {e}\nHeres your random link: {}", gif_direct_link);
+							process::exit(1);
+						}
+					} else if env::var_os("WAYLAND_DISPLAY").is_some() {
+						if let Err(e) = wayland_copy_image_to_clipboard(&image_bytes, mime) {
+							eprintln!("An error occured when calling `wl-copy`: {e}\nHeres your random link: {}", gif_direct_link);
+							process::exit(1);
+						}
+					} else {
+						eprintln!("Failed to detect display server, are DISPLAY or WAYLAND_DISPLAY set?\nHeres your random link: {}", gif_direct_link);
+						process::exit(1);
+					}
+				},
+				"windows"|"macos" => {
+					if let Err(e) = native_copy_image_to_clipboard(&image_bytes) {
+						eprintln!("Couldn't place the image on the clipboard: {e}\nHeres your random link: {}", gif_direct_link);
+						process::exit(1);
+					}
+				},
+				_ => {} // this path is already handled above
+			}
+		} else if args.copy_random {
 			match os {
 				"linux"|"openbsd"|"freebsd"|"netbsd" => {
 					if env::var_os("DISPLAY").is_some() {
@@ -334,27 +759,68 @@ async fn main () -> Result<(), Error> {
 	
 		if args.save_random {
 			let picture_dir = dirs_next::picture_dir().expect("dirs_next couldnt get a picture dir");
-			let client = reqwest::Client::new();
-			let response = client
-				.get(gif_direct_link)
-				.header(USER_AGENT, "rust-web-api-client")
-				.send()
-				.await?;
+
+			// Detect a missing ffmpeg up front so we don't download before failing
+			if args.convert.is_some() && Command::new("ffmpeg").arg("-version").stdout(Stdio::null()).stderr(Stdio::null()).status().is_err() {
+				eprintln!("`ffmpeg` is required for --convert but couldn't be run. Install it and try again.\nHeres your random link: {}", gif_direct_link);
+				process::exit(1);
+			}
 
 			let mut filename = gif_direct_link.split("/").last().unwrap().to_string().clone();
 			let mut path = picture_dir.join(&filename);
+			//apply the converted extension first so the collision check is against the real output name
+			if let Some(format) = args.convert {
+				path.set_extension(format.extension());
+				filename = path.file_name().unwrap().to_string_lossy().into_owned();
+			}
 			if Path::new(&path).exists() {
 				let random = random_range(0..=100000).to_string();
-				filename.insert_str(filename.len()-4, &random);
+				let extension_len = Path::new(&filename).extension().map_or(0, |ext| ext.len() + 1);
+				filename.insert_str(filename.len() - extension_len, &random);
 				path = picture_dir.join(&filename);
 			}
 
+			let response_bytes = download_bytes(&client, gif_direct_link).await?;
+			let file_bytes = match args.convert {
+				Some(format) => {
+					match transcode_with_ffmpeg(&response_bytes, format) {
+						Ok(converted) => converted,
+						Err(e) => {
+							eprintln!("ffmpeg failed to convert the media: {e}\nHeres your random link: {}", gif_direct_link);
+							process::exit(1);
+						}
+					}
+				}
+				None => response_bytes.to_vec(),
+			};
+
 			let mut file = File::create(&path).expect("Failed to create file");
-			
-			let response_bytes = response.bytes().await?;
-			file.write_all(&response_bytes).expect("Couldn't write to file");
+			file.write_all(&file_bytes).expect("Couldn't write to file");
 			println!("Saved file to {:?}", &path);
 		}
+
+		if args.post {
+			let instance = std::env::var("MASTODON_INSTANCE").unwrap_or_else(|_| {
+				eprintln!("No Mastodon instance configured, set one with --set-mastodon-instance <URL>\nHeres your random link: {}", gif_direct_link);
+				process::exit(1);
+			});
+			let token = std::env::var("MASTODON_TOKEN").unwrap_or_else(|_| {
+				eprintln!("No Mastodon token configured, set one with --set-mastodon-token <TOKEN>\nHeres your random link: {}", gif_direct_link);
+				process::exit(1);
+			});
+
+			let bytes = download_bytes(&client, gif_direct_link).await?;
+			let mime = mime_for_url(gif_direct_link);
+			let filename = gif_direct_link.split("/").last().unwrap();
+			let status = args.caption.as_deref().unwrap_or(&random_gif.content_description);
+			match post_to_mastodon(&client, &instance, &token, bytes, mime, filename, &random_gif.content_description, status).await {
+				Ok(url) => println!("Posted to {}", url),
+				Err(e) => {
+					eprintln!("Failed to post to Mastodon: {e}\nHeres your random link: {}", gif_direct_link);
+					process::exit(1);
+				}
+			}
+		}
 	}
 
 	Ok(())